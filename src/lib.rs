@@ -1,14 +1,31 @@
 //! byte-io: a simple crate for read/write numbers to/from binary.
 //!
-//! This crate only contains 4 functions:
+//! The core API is a handful of free functions for reading and writing
+//! fixed-width numbers to/from a slice:
 //!
-//! * `write_be`: write number to big-endian slice.
+//! * `write_be` / `read_be`: write/read a number as big-endian.
 //!
-//! * `read_be`: read number from big-endian slice.
+//! * `write_le` / `read_le`: write/read a number as little-endian.
 //!
-//! * `write_le`: write number to little-endian slice.
+//! These panic on a short buffer; `try_write_be`/`try_read_be` and
+//! `try_write_le`/`try_read_le` return a `Result<_, ByteError>` instead.
 //!
-//! * `read_le`: read number from little-endian slice.
+//! On top of that:
+//!
+//! * `read_be_from`/`write_be_to` and `read_le_from`/`write_le_to` read from
+//!   / write to any `std::io::Read`/`Write` stream.
+//!
+//! * `read`/`write` take the byte order as a generic `ByteOrder` type
+//!   parameter (`BigEndian`/`LittleEndian`) instead of being named per
+//!   endianness.
+//!
+//! * `write_leb128`/`read_leb128`/`max_leb128_len` encode/decode integers as
+//!   LEB128 varints.
+//!
+//! * `BitReader`/`BitWriter` read and write sub-byte (bit-level) fields.
+//!
+//! Numbers are converted via the `Readable`/`Writeable` traits, which are
+//! also implemented for `Vec<T>` of fixed-size elements.
 //!
 //! ## Examples:
 //!
@@ -72,14 +89,65 @@
 //!
 //! ## Implementation Details
 //!
-//! byte-io does __NOT__ focus on efficiency, which means that it may be slow
-//! while handling big streams (e.g. hundreds of Mbytes or more).
+//! Multi-byte numbers are converted with a single `copy_nonoverlapping` plus
+//! `to_be`/`to_le`, so reading/writing `Vec<T>` boils down to one memcpy per
+//! element rather than per-byte shifting.
 //!
 //! Generally speaking, byte-io implements the two traits for numbers: `Readable` and
 //! `Writeable`. Every type implements these two traits can be deceded/enceded from
 //! binary stream.
+use std::error;
+use std::fmt;
+use std::io::{self, Read, Write};
 use std::marker;
-use std::mem::{size_of, transmute};
+use std::mem::size_of;
+use std::ptr::copy_nonoverlapping;
+
+/// The error returned by the fallible `try_*` variants of this crate's
+/// read/write functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteError {
+    /// the buffer ran out of bytes before the value could be fully
+    /// read/written. `needed` is the number of bytes the operation
+    /// required, `got` is the number of bytes actually available.
+    UnexpectedEof { needed: usize, got: usize },
+
+    /// a `Vec<T>` was asked to read/write elements whose `T::SIZE` is `0`
+    /// (e.g. another variable-length type such as `Vec<Vec<u16>>`). There is
+    /// no way to tell where one zero-sized element ends and the next
+    /// begins, so this is rejected rather than looping forever.
+    UnsupportedElementSize,
+
+    /// a LEB128-encoded value used more continuation bytes than any
+    /// well-formed varint for this crate's integer types ever needs. This
+    /// only happens for malformed or adversarial input (e.g. a run of
+    /// bytes with the high bit always set), so it is rejected rather than
+    /// shifting by more bits than the accumulator holds.
+    Leb128Overflow { max_bytes: usize },
+}
+
+impl fmt::Display for ByteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ByteError::UnexpectedEof { needed, got } => write!(
+                f,
+                "unexpected end of buffer: needed {} bytes, got {}",
+                needed, got
+            ),
+            ByteError::UnsupportedElementSize => write!(
+                f,
+                "cannot read/write a Vec of zero-sized (variable-length) elements"
+            ),
+            ByteError::Leb128Overflow { max_bytes } => write!(
+                f,
+                "malformed leb128: needs more than the maximum {} bytes",
+                max_bytes
+            ),
+        }
+    }
+}
+
+impl error::Error for ByteError {}
 
 /// write a number to stream as big-endian.
 ///
@@ -93,7 +161,7 @@ use std::mem::{size_of, transmute};
 /// assert_eq!(buf, [0,0,0,0,0,0,0,1]);
 /// ```
 pub fn write_be<T: Writeable>(v: &T, buffer: &mut [u8]) {
-    T::to_u8_be(v, buffer);
+    BigEndian::write(v, buffer);
 }
 
 /// read a number from stream as big-endian.
@@ -107,7 +175,7 @@ pub fn write_be<T: Writeable>(v: &T, buffer: &mut [u8]) {
 /// assert_eq!(read_be::<i16>(&data[3..]), 0x0123);
 /// ```
 pub fn read_be<T: Readable>(buffer: &[u8]) -> T {
-    T::from_u8_be(buffer)
+    BigEndian::read(buffer)
 }
 
 /// write a number to stream as little-endian.
@@ -122,7 +190,7 @@ pub fn read_be<T: Readable>(buffer: &[u8]) -> T {
 /// assert_eq!(buf, [1,0,0,0,0,0,0,0]);
 /// ```
 pub fn write_le<T: Writeable>(v: &T, buffer: &mut [u8]) {
-    T::to_u8_le(v, buffer);
+    LittleEndian::write(v, buffer);
 }
 
 /// read a number from stream as big-endian.
@@ -136,334 +204,1103 @@ pub fn write_le<T: Writeable>(v: &T, buffer: &mut [u8]) {
 /// assert_eq!(read_le::<i16>(&data[3..]), 0x2301);
 /// ```
 pub fn read_le<T: Readable>(buffer: &[u8]) -> T {
-    T::from_u8_le(buffer)
+    LittleEndian::read(buffer)
+}
+
+/// write a number to stream as big-endian, without panicking.
+///
+/// returns `Err(ByteError::UnexpectedEof { .. })` if buffer does not
+/// contain enough space.
+///
+/// ```
+/// use byte_io::*;
+///
+/// let mut buf = [0u8; 2];
+/// assert!(try_write_be(&1u64, &mut buf).is_err());
+/// assert!(try_write_be(&1u16, &mut buf).is_ok());
+/// ```
+pub fn try_write_be<T: Writeable>(v: &T, buffer: &mut [u8]) -> Result<(), ByteError> {
+    T::try_to_u8_be(v, buffer)
+}
+
+/// read a number from stream as big-endian, without panicking.
+///
+/// returns `Err(ByteError::UnexpectedEof { .. })` if buffer does not
+/// contain enough bytes.
+///
+/// ```
+/// use byte_io::*;
+///
+/// let data = [0xAB, 0xCD];
+/// assert!(try_read_be::<u32>(&data).is_err());
+/// assert_eq!(try_read_be::<u16>(&data), Ok(0xABCD));
+/// ```
+pub fn try_read_be<T: Readable>(buffer: &[u8]) -> Result<T, ByteError> {
+    T::try_from_u8_be(buffer)
+}
+
+/// write a number to stream as little-endian, without panicking.
+///
+/// returns `Err(ByteError::UnexpectedEof { .. })` if buffer does not
+/// contain enough space.
+///
+/// ```
+/// use byte_io::*;
+///
+/// let mut buf = [0u8; 2];
+/// assert!(try_write_le(&1u64, &mut buf).is_err());
+/// assert!(try_write_le(&1u16, &mut buf).is_ok());
+/// ```
+pub fn try_write_le<T: Writeable>(v: &T, buffer: &mut [u8]) -> Result<(), ByteError> {
+    T::try_to_u8_le(v, buffer)
+}
+
+/// read a number from stream as little-endian, without panicking.
+///
+/// returns `Err(ByteError::UnexpectedEof { .. })` if buffer does not
+/// contain enough bytes.
+///
+/// ```
+/// use byte_io::*;
+///
+/// let data = [0xAB, 0xCD];
+/// assert!(try_read_le::<u32>(&data).is_err());
+/// assert_eq!(try_read_le::<u16>(&data), Ok(0xCDAB));
+/// ```
+pub fn try_read_le<T: Readable>(buffer: &[u8]) -> Result<T, ByteError> {
+    T::try_from_u8_le(buffer)
+}
+
+/// read a number as big-endian directly from a `std::io::Read`, e.g. a file
+/// or socket, without having to manage an intermediate buffer.
+///
+/// ```
+/// use std::io::Cursor;
+/// use byte_io::*;
+///
+/// let mut reader = Cursor::new([0x00, 0x00, 0x01, 0x01]);
+/// assert_eq!(read_be_from::<u32, _>(&mut reader).unwrap(), 0x0101);
+/// ```
+pub fn read_be_from<T: Readable, R: Read>(reader: &mut R) -> io::Result<T> {
+    let mut buffer = vec![0u8; T::SIZE];
+    reader.read_exact(&mut buffer)?;
+    Ok(T::from_u8_be(&buffer))
+}
+
+/// read a number as little-endian directly from a `std::io::Read`, e.g. a
+/// file or socket, without having to manage an intermediate buffer.
+///
+/// ```
+/// use std::io::Cursor;
+/// use byte_io::*;
+///
+/// let mut reader = Cursor::new([0x01, 0x01, 0x00, 0x00]);
+/// assert_eq!(read_le_from::<u32, _>(&mut reader).unwrap(), 0x0101);
+/// ```
+pub fn read_le_from<T: Readable, R: Read>(reader: &mut R) -> io::Result<T> {
+    let mut buffer = vec![0u8; T::SIZE];
+    reader.read_exact(&mut buffer)?;
+    Ok(T::from_u8_le(&buffer))
+}
+
+/// write a number as big-endian directly to a `std::io::Write`, e.g. a file
+/// or socket, without having to manage an intermediate buffer.
+///
+/// ```
+/// use byte_io::*;
+///
+/// let mut buffer = Vec::new();
+/// write_be_to(&0x0101u32, &mut buffer).unwrap();
+/// assert_eq!(buffer, [0x00, 0x00, 0x01, 0x01]);
+/// ```
+pub fn write_be_to<T: Writeable, W: Write>(v: &T, writer: &mut W) -> io::Result<()> {
+    let mut buffer = vec![0u8; T::SIZE];
+    T::to_u8_be(v, &mut buffer);
+    writer.write_all(&buffer)
+}
+
+/// write a number as little-endian directly to a `std::io::Write`, e.g. a
+/// file or socket, without having to manage an intermediate buffer.
+///
+/// ```
+/// use byte_io::*;
+///
+/// let mut buffer = Vec::new();
+/// write_le_to(&0x0101u32, &mut buffer).unwrap();
+/// assert_eq!(buffer, [0x01, 0x01, 0x00, 0x00]);
+/// ```
+pub fn write_le_to<T: Writeable, W: Write>(v: &T, writer: &mut W) -> io::Result<()> {
+    let mut buffer = vec![0u8; T::SIZE];
+    T::to_u8_le(v, &mut buffer);
+    writer.write_all(&buffer)
 }
 
 /// Any type implementing Readable can be decoded from binary.
-pub trait Readable : marker::Sized {
-    fn from_u8_be(&[u8]) -> Self;
-    fn from_u8_le(&[u8]) -> Self;
+pub trait Readable: marker::Sized {
+    /// the number of bytes a single encoded value occupies on the wire.
+    /// `0` for variable-length types (e.g. `Vec<T>`), which have no fixed
+    /// size of their own — callers sizing a container's elements should use
+    /// the *element* type's `SIZE` instead. There is deliberately no default
+    /// so every impl states its wire size explicitly.
+    const SIZE: usize;
+
+    /// panics if buffer does not contain enough bytes. See `try_from_u8_be`
+    /// for a fallible version.
+    fn from_u8_be(buffer: &[u8]) -> Self {
+        Self::try_from_u8_be(buffer).unwrap()
+    }
+
+    /// panics if buffer does not contain enough bytes. See `try_from_u8_le`
+    /// for a fallible version.
+    fn from_u8_le(buffer: &[u8]) -> Self {
+        Self::try_from_u8_le(buffer).unwrap()
+    }
+
+    fn try_from_u8_be(buffer: &[u8]) -> Result<Self, ByteError>;
+    fn try_from_u8_le(buffer: &[u8]) -> Result<Self, ByteError>;
 }
 
 /// Any type implementing Writeable can be encoded from binary.
-pub trait Writeable : marker::Sized {
-    fn to_u8_be(&Self, &mut [u8]);
-    fn to_u8_le(&Self, &mut [u8]);
+pub trait Writeable: marker::Sized {
+    /// the number of bytes a single encoded value occupies on the wire.
+    /// `0` for variable-length types (e.g. `Vec<T>`), which have no fixed
+    /// size of their own — callers sizing a container's elements should use
+    /// the *element* type's `SIZE` instead. There is deliberately no default
+    /// so every impl states its wire size explicitly.
+    const SIZE: usize;
+
+    /// panics if buffer does not contain enough space. See `try_to_u8_be`
+    /// for a fallible version.
+    fn to_u8_be(v: &Self, buffer: &mut [u8]) {
+        Self::try_to_u8_be(v, buffer).unwrap()
+    }
+
+    /// panics if buffer does not contain enough space. See `try_to_u8_le`
+    /// for a fallible version.
+    fn to_u8_le(v: &Self, buffer: &mut [u8]) {
+        Self::try_to_u8_le(v, buffer).unwrap()
+    }
+
+    fn try_to_u8_be(v: &Self, buffer: &mut [u8]) -> Result<(), ByteError>;
+    fn try_to_u8_le(v: &Self, buffer: &mut [u8]) -> Result<(), ByteError>;
 }
 
+/// Implemented by the zero-sized marker types `BigEndian`/`LittleEndian`
+/// to select which byte order `read`/`write` should use, so that code
+/// generic over endianness can thread it through a type parameter instead
+/// of branching between `read_be`/`read_le` by hand.
+pub trait ByteOrder {
+    fn read<T: Readable>(buffer: &[u8]) -> T;
+    fn write<T: Writeable>(v: &T, buffer: &mut [u8]);
+}
+
+/// Marker type for big-endian byte order, see `ByteOrder`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BigEndian;
+
+/// Marker type for little-endian byte order, see `ByteOrder`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LittleEndian;
+
+impl ByteOrder for BigEndian {
+    fn read<T: Readable>(buffer: &[u8]) -> T {
+        T::from_u8_be(buffer)
+    }
+
+    fn write<T: Writeable>(v: &T, buffer: &mut [u8]) {
+        T::to_u8_be(v, buffer)
+    }
+}
+
+impl ByteOrder for LittleEndian {
+    fn read<T: Readable>(buffer: &[u8]) -> T {
+        T::from_u8_le(buffer)
+    }
+
+    fn write<T: Writeable>(v: &T, buffer: &mut [u8]) {
+        T::to_u8_le(v, buffer)
+    }
+}
+
+/// The byte order of the target platform, resolved at compile time to
+/// `BigEndian` or `LittleEndian`.
+#[cfg(target_endian = "big")]
+pub type NativeEndian = BigEndian;
+
+/// The byte order of the target platform, resolved at compile time to
+/// `BigEndian` or `LittleEndian`.
+#[cfg(target_endian = "little")]
+pub type NativeEndian = LittleEndian;
+
+/// read a number from stream, generic over the byte order `E`.
+///
+/// ```
+/// use byte_io::*;
+///
+/// let data = [0xAB, 0xCD, 0xEF, 0x01];
+/// assert_eq!(read::<u32, BigEndian>(&data), 0xABCDEF01);
+/// assert_eq!(read::<u32, LittleEndian>(&data), 0x01EFCDAB);
+/// ```
+pub fn read<T: Readable, E: ByteOrder>(buffer: &[u8]) -> T {
+    E::read(buffer)
+}
+
+/// write a number to stream, generic over the byte order `E`.
+///
+/// ```
+/// use byte_io::*;
+///
+/// let mut buf = [0u8; 4];
+/// write::<u32, BigEndian>(&0xABCDEF01, &mut buf);
+/// assert_eq!(buf, [0xAB, 0xCD, 0xEF, 0x01]);
+/// ```
+pub fn write<T: Writeable, E: ByteOrder>(v: &T, buffer: &mut [u8]) {
+    E::write(v, buffer)
+}
 
 impl<T: Readable> Readable for Vec<T> {
-    fn from_u8_be(input: &[u8]) -> Self {
-        let t_size = size_of::<T>();
+    const SIZE: usize = 0;
+
+    fn try_from_u8_be(input: &[u8]) -> Result<Self, ByteError> {
+        let t_size = T::SIZE;
+        if t_size == 0 && !input.is_empty() {
+            return Err(ByteError::UnsupportedElementSize);
+        }
         let mut output = Vec::new();
-        for i in 0..input.len() / t_size {
-            output.push(T::from_u8_be(&input[i * t_size..(i + 1) * t_size]));
+        let mut offset = 0;
+        while offset < input.len() {
+            if input.len() - offset < t_size {
+                return Err(ByteError::UnexpectedEof {
+                    needed: t_size,
+                    got: input.len() - offset,
+                });
+            }
+            output.push(T::try_from_u8_be(&input[offset..offset + t_size])?);
+            offset += t_size;
         }
-        output
+        Ok(output)
     }
 
-    fn from_u8_le(input: &[u8]) -> Self {
-        let t_size = size_of::<T>();
+    fn try_from_u8_le(input: &[u8]) -> Result<Self, ByteError> {
+        let t_size = T::SIZE;
+        if t_size == 0 && !input.is_empty() {
+            return Err(ByteError::UnsupportedElementSize);
+        }
         let mut output = Vec::new();
-        for i in 0..input.len() / t_size {
-            output.push(T::from_u8_le(&input[i * t_size..(i + 1) * t_size]));
+        let mut offset = 0;
+        while offset < input.len() {
+            if input.len() - offset < t_size {
+                return Err(ByteError::UnexpectedEof {
+                    needed: t_size,
+                    got: input.len() - offset,
+                });
+            }
+            output.push(T::try_from_u8_le(&input[offset..offset + t_size])?);
+            offset += t_size;
         }
-        output
+        Ok(output)
     }
 }
 
 impl<T: Writeable> Writeable for Vec<T> {
-    fn to_u8_be(v: &Self, buf: &mut [u8]) {
-        let t_size = size_of::<T>();
-        for (i, v) in v.iter().enumerate() {
-            T::to_u8_be(v, &mut buf[i * t_size..(i + 1) * t_size]);
+    const SIZE: usize = 0;
+
+    fn try_to_u8_be(v: &Self, buf: &mut [u8]) -> Result<(), ByteError> {
+        let t_size = T::SIZE;
+        if t_size == 0 && !v.is_empty() {
+            return Err(ByteError::UnsupportedElementSize);
+        }
+        for (i, item) in v.iter().enumerate() {
+            let start = i * t_size;
+            if buf.len() < start + t_size {
+                return Err(ByteError::UnexpectedEof {
+                    needed: t_size,
+                    got: buf.len().saturating_sub(start),
+                });
+            }
+            T::try_to_u8_be(item, &mut buf[start..start + t_size])?;
         }
+        Ok(())
     }
 
-    fn to_u8_le(v: &Self, buf: &mut [u8]) {
-        let t_size = size_of::<T>();
-        for (i, v) in v.iter().enumerate() {
-            T::to_u8_le(v, &mut buf[i * t_size..(i + 1) * t_size]);
+    fn try_to_u8_le(v: &Self, buf: &mut [u8]) -> Result<(), ByteError> {
+        let t_size = T::SIZE;
+        if t_size == 0 && !v.is_empty() {
+            return Err(ByteError::UnsupportedElementSize);
         }
+        for (i, item) in v.iter().enumerate() {
+            let start = i * t_size;
+            if buf.len() < start + t_size {
+                return Err(ByteError::UnexpectedEof {
+                    needed: t_size,
+                    got: buf.len().saturating_sub(start),
+                });
+            }
+            T::try_to_u8_le(item, &mut buf[start..start + t_size])?;
+        }
+        Ok(())
+    }
+}
+
+fn check_len(buffer: &[u8], needed: usize) -> Result<(), ByteError> {
+    if buffer.len() < needed {
+        Err(ByteError::UnexpectedEof {
+            needed,
+            got: buffer.len(),
+        })
+    } else {
+        Ok(())
     }
 }
 
 impl Writeable for i8 {
-    fn to_u8_be(v: &Self, a: &mut [u8]) {
+    const SIZE: usize = 1;
+
+    fn try_to_u8_be(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        check_len(a, 1)?;
         a[0] = *v as u8;
+        Ok(())
     }
 
-    fn to_u8_le(v: &Self, a: &mut [u8]) {
+    fn try_to_u8_le(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        check_len(a, 1)?;
         a[0] = *v as u8;
+        Ok(())
     }
 }
 
 impl Readable for i8 {
-    fn from_u8_be(i: &[u8]) -> Self {
-        i[0] as i8
+    const SIZE: usize = 1;
+
+    fn try_from_u8_be(i: &[u8]) -> Result<Self, ByteError> {
+        check_len(i, 1)?;
+        Ok(i[0] as i8)
     }
 
-    fn from_u8_le(i: &[u8]) -> Self {
-        i[0] as i8
+    fn try_from_u8_le(i: &[u8]) -> Result<Self, ByteError> {
+        check_len(i, 1)?;
+        Ok(i[0] as i8)
     }
 }
 
 impl Readable for u8 {
-    fn from_u8_be(a: &[u8]) -> Self {
-        a[0]
+    const SIZE: usize = 1;
+
+    fn try_from_u8_be(a: &[u8]) -> Result<Self, ByteError> {
+        check_len(a, 1)?;
+        Ok(a[0])
     }
 
-    fn from_u8_le(a: &[u8]) -> Self {
-        a[0]
+    fn try_from_u8_le(a: &[u8]) -> Result<Self, ByteError> {
+        check_len(a, 1)?;
+        Ok(a[0])
     }
 }
 
 impl Writeable for u8 {
-    fn to_u8_be(v: &Self, a: &mut [u8]) {
+    const SIZE: usize = 1;
+
+    fn try_to_u8_be(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        check_len(a, 1)?;
         a[0] = *v;
+        Ok(())
     }
 
-    fn to_u8_le(v: &Self, a: &mut [u8]) {
+    fn try_to_u8_le(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        check_len(a, 1)?;
         a[0] = *v;
+        Ok(())
     }
 }
 
 impl Readable for i16 {
-    fn from_u8_be(i: &[u8]) -> Self {
-        (i[0] as i16) << 8 | i[1] as i16
+    const SIZE: usize = 2;
+
+    fn try_from_u8_be(i: &[u8]) -> Result<Self, ByteError> {
+        check_len(i, 2)?;
+        let mut v: i16 = 0;
+        unsafe { copy_nonoverlapping(i.as_ptr(), &mut v as *mut i16 as *mut u8, 2) };
+        Ok(v.to_be())
     }
 
-    fn from_u8_le(i: &[u8]) -> Self {
-        (i[1] as i16) << 8 | i[0] as i16
+    fn try_from_u8_le(i: &[u8]) -> Result<Self, ByteError> {
+        check_len(i, 2)?;
+        let mut v: i16 = 0;
+        unsafe { copy_nonoverlapping(i.as_ptr(), &mut v as *mut i16 as *mut u8, 2) };
+        Ok(v.to_le())
     }
 }
 
 impl Writeable for i16 {
-    fn to_u8_be(v: &Self, a: &mut [u8]) {
-        a[0] = (*v >> 8) as u8;
-        a[1] = *v as u8;
+    const SIZE: usize = 2;
+
+    fn try_to_u8_be(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        check_len(a, 2)?;
+        let be = v.to_be();
+        unsafe { copy_nonoverlapping(&be as *const i16 as *const u8, a.as_mut_ptr(), 2) };
+        Ok(())
     }
 
-    fn to_u8_le(v: &Self, a: &mut [u8]) {
-        a[1] = (*v >> 8) as u8;
-        a[0] = *v as u8;
+    fn try_to_u8_le(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        check_len(a, 2)?;
+        let le = v.to_le();
+        unsafe { copy_nonoverlapping(&le as *const i16 as *const u8, a.as_mut_ptr(), 2) };
+        Ok(())
     }
 }
 
 impl Readable for u16 {
-    fn from_u8_be(i: &[u8]) -> Self {
-        (i[0] as u16) << 8 | i[1] as u16
+    const SIZE: usize = 2;
+
+    fn try_from_u8_be(i: &[u8]) -> Result<Self, ByteError> {
+        check_len(i, 2)?;
+        let mut v: u16 = 0;
+        unsafe { copy_nonoverlapping(i.as_ptr(), &mut v as *mut u16 as *mut u8, 2) };
+        Ok(v.to_be())
     }
 
-    fn from_u8_le(i: &[u8]) -> Self {
-        (i[1] as u16) << 8 | i[0] as u16
+    fn try_from_u8_le(i: &[u8]) -> Result<Self, ByteError> {
+        check_len(i, 2)?;
+        let mut v: u16 = 0;
+        unsafe { copy_nonoverlapping(i.as_ptr(), &mut v as *mut u16 as *mut u8, 2) };
+        Ok(v.to_le())
     }
 }
 
 impl Writeable for u16 {
-    fn to_u8_be(v: &Self, a: &mut [u8]) {
-        a[0] = (*v >> 8) as u8;
-        a[1] = *v as u8;
+    const SIZE: usize = 2;
+
+    fn try_to_u8_be(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        check_len(a, 2)?;
+        let be = v.to_be();
+        unsafe { copy_nonoverlapping(&be as *const u16 as *const u8, a.as_mut_ptr(), 2) };
+        Ok(())
     }
 
-    fn to_u8_le(v: &Self, a: &mut [u8]) {
-        a[1] = (*v >> 8) as u8;
-        a[0] = *v as u8;
+    fn try_to_u8_le(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        check_len(a, 2)?;
+        let le = v.to_le();
+        unsafe { copy_nonoverlapping(&le as *const u16 as *const u8, a.as_mut_ptr(), 2) };
+        Ok(())
     }
 }
 
 impl Readable for i32 {
-    fn from_u8_be(i: &[u8]) -> Self {
-        (i[0] as i32) << 24 | (i[1] as i32) << 16 | (i[2] as i32) << 8 | i[3] as i32
+    const SIZE: usize = 4;
+
+    fn try_from_u8_be(i: &[u8]) -> Result<Self, ByteError> {
+        check_len(i, 4)?;
+        let mut v: i32 = 0;
+        unsafe { copy_nonoverlapping(i.as_ptr(), &mut v as *mut i32 as *mut u8, 4) };
+        Ok(v.to_be())
     }
 
-    fn from_u8_le(i: &[u8]) -> Self {
-        (i[3] as i32) << 24 | (i[2] as i32) << 16 | (i[1] as i32) << 8 | i[0] as i32
+    fn try_from_u8_le(i: &[u8]) -> Result<Self, ByteError> {
+        check_len(i, 4)?;
+        let mut v: i32 = 0;
+        unsafe { copy_nonoverlapping(i.as_ptr(), &mut v as *mut i32 as *mut u8, 4) };
+        Ok(v.to_le())
     }
 }
 
 impl Writeable for i32 {
-    fn to_u8_be(v: &Self, a: &mut [u8]) {
-        a[0] = (*v >> 24) as u8;
-        a[1] = (*v >> 16) as u8;
-        a[2] = (*v >> 8) as u8;
-        a[3] = *v as u8;
+    const SIZE: usize = 4;
+
+    fn try_to_u8_be(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        check_len(a, 4)?;
+        let be = v.to_be();
+        unsafe { copy_nonoverlapping(&be as *const i32 as *const u8, a.as_mut_ptr(), 4) };
+        Ok(())
     }
 
-    fn to_u8_le(v: &Self, a: &mut [u8]) {
-        a[3] = (*v >> 24) as u8;
-        a[2] = (*v >> 16) as u8;
-        a[1] = (*v >> 8) as u8;
-        a[0] = *v as u8;
+    fn try_to_u8_le(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        check_len(a, 4)?;
+        let le = v.to_le();
+        unsafe { copy_nonoverlapping(&le as *const i32 as *const u8, a.as_mut_ptr(), 4) };
+        Ok(())
     }
 }
 
 impl Readable for u32 {
-    fn from_u8_be(i: &[u8]) -> Self {
-        (i[0] as u32) << 24 | (i[1] as u32) << 16 | (i[2] as u32) << 8 | i[3] as u32
+    const SIZE: usize = 4;
+
+    fn try_from_u8_be(i: &[u8]) -> Result<Self, ByteError> {
+        check_len(i, 4)?;
+        let mut v: u32 = 0;
+        unsafe { copy_nonoverlapping(i.as_ptr(), &mut v as *mut u32 as *mut u8, 4) };
+        Ok(v.to_be())
     }
 
-    fn from_u8_le(i: &[u8]) -> Self {
-        (i[3] as u32) << 24 | (i[2] as u32) << 16 | (i[1] as u32) << 8 | i[0] as u32
+    fn try_from_u8_le(i: &[u8]) -> Result<Self, ByteError> {
+        check_len(i, 4)?;
+        let mut v: u32 = 0;
+        unsafe { copy_nonoverlapping(i.as_ptr(), &mut v as *mut u32 as *mut u8, 4) };
+        Ok(v.to_le())
     }
 }
 
 impl Writeable for u32 {
-    fn to_u8_be(v: &Self, a: &mut [u8]) {
-        a[0] = (*v >> 24) as u8;
-        a[1] = (*v >> 16) as u8;
-        a[2] = (*v >> 8) as u8;
-        a[3] = *v as u8;
+    const SIZE: usize = 4;
+
+    fn try_to_u8_be(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        check_len(a, 4)?;
+        let be = v.to_be();
+        unsafe { copy_nonoverlapping(&be as *const u32 as *const u8, a.as_mut_ptr(), 4) };
+        Ok(())
     }
 
-    fn to_u8_le(v: &Self, a: &mut [u8]) {
-        a[3] = (*v >> 24) as u8;
-        a[2] = (*v >> 16) as u8;
-        a[1] = (*v >> 8) as u8;
-        a[0] = *v as u8;
+    fn try_to_u8_le(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        check_len(a, 4)?;
+        let le = v.to_le();
+        unsafe { copy_nonoverlapping(&le as *const u32 as *const u8, a.as_mut_ptr(), 4) };
+        Ok(())
     }
 }
 
 impl Readable for i64 {
-    fn from_u8_be(i: &[u8]) -> Self {
-        (i[0] as i64) << 56 | (i[1] as i64) << 48 | (i[2] as i64) << 40 | (i[3] as i64) << 32 |
-        (i[4] as i64) << 24 | (i[5] as i64) << 16 |
-        (i[6] as i64) << 8 | i[7] as i64
+    const SIZE: usize = 8;
+
+    fn try_from_u8_be(i: &[u8]) -> Result<Self, ByteError> {
+        check_len(i, 8)?;
+        let mut v: i64 = 0;
+        unsafe { copy_nonoverlapping(i.as_ptr(), &mut v as *mut i64 as *mut u8, 8) };
+        Ok(v.to_be())
     }
 
-    fn from_u8_le(i: &[u8]) -> Self {
-        (i[7] as i64) << 56 | (i[6] as i64) << 48 | (i[5] as i64) << 40 | (i[4] as i64) << 32 |
-        (i[3] as i64) << 24 | (i[2] as i64) << 16 |
-        (i[1] as i64) << 8 | i[0] as i64
+    fn try_from_u8_le(i: &[u8]) -> Result<Self, ByteError> {
+        check_len(i, 8)?;
+        let mut v: i64 = 0;
+        unsafe { copy_nonoverlapping(i.as_ptr(), &mut v as *mut i64 as *mut u8, 8) };
+        Ok(v.to_le())
     }
 }
 
 impl Writeable for i64 {
-    fn to_u8_be(v: &Self, a: &mut [u8]) {
-        a[0] = (*v >> 56) as u8;
-        a[1] = (*v >> 48) as u8;
-        a[2] = (*v >> 40) as u8;
-        a[3] = (*v >> 32) as u8;
-        a[4] = (*v >> 24) as u8;
-        a[5] = (*v >> 16) as u8;
-        a[6] = (*v >> 8) as u8;
-        a[7] = *v as u8;
-    }
-
-    fn to_u8_le(v: &Self, a: &mut [u8]) {
-        a[7] = (*v >> 56) as u8;
-        a[6] = (*v >> 48) as u8;
-        a[5] = (*v >> 40) as u8;
-        a[4] = (*v >> 32) as u8;
-        a[3] = (*v >> 24) as u8;
-        a[2] = (*v >> 16) as u8;
-        a[1] = (*v >> 8) as u8;
-        a[0] = *v as u8;
+    const SIZE: usize = 8;
+
+    fn try_to_u8_be(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        check_len(a, 8)?;
+        let be = v.to_be();
+        unsafe { copy_nonoverlapping(&be as *const i64 as *const u8, a.as_mut_ptr(), 8) };
+        Ok(())
+    }
+
+    fn try_to_u8_le(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        check_len(a, 8)?;
+        let le = v.to_le();
+        unsafe { copy_nonoverlapping(&le as *const i64 as *const u8, a.as_mut_ptr(), 8) };
+        Ok(())
     }
 }
 
 impl Readable for u64 {
-    fn from_u8_be(i: &[u8]) -> Self {
-        (i[0] as u64) << 56 | (i[1] as u64) << 48 | (i[2] as u64) << 40 | (i[3] as u64) << 32 |
-        (i[4] as u64) << 24 | (i[5] as u64) << 16 |
-        (i[6] as u64) << 8 | i[7] as u64
+    const SIZE: usize = 8;
+
+    fn try_from_u8_be(i: &[u8]) -> Result<Self, ByteError> {
+        check_len(i, 8)?;
+        let mut v: u64 = 0;
+        unsafe { copy_nonoverlapping(i.as_ptr(), &mut v as *mut u64 as *mut u8, 8) };
+        Ok(v.to_be())
     }
 
-    fn from_u8_le(i: &[u8]) -> Self {
-        (i[7] as u64) << 56 | (i[6] as u64) << 48 | (i[5] as u64) << 40 | (i[4] as u64) << 32 |
-        (i[3] as u64) << 24 | (i[2] as u64) << 16 |
-        (i[1] as u64) << 8 | i[0] as u64
+    fn try_from_u8_le(i: &[u8]) -> Result<Self, ByteError> {
+        check_len(i, 8)?;
+        let mut v: u64 = 0;
+        unsafe { copy_nonoverlapping(i.as_ptr(), &mut v as *mut u64 as *mut u8, 8) };
+        Ok(v.to_le())
     }
 }
 
 impl Writeable for u64 {
-    fn to_u8_be(v: &Self, a: &mut [u8]) {
-        a[0] = (*v >> 56) as u8;
-        a[1] = (*v >> 48) as u8;
-        a[2] = (*v >> 40) as u8;
-        a[3] = (*v >> 32) as u8;
-        a[4] = (*v >> 24) as u8;
-        a[5] = (*v >> 16) as u8;
-        a[6] = (*v >> 8) as u8;
-        a[7] = *v as u8;
-    }
-
-    fn to_u8_le(v: &Self, a: &mut [u8]) {
-        a[7] = (*v >> 56) as u8;
-        a[6] = (*v >> 48) as u8;
-        a[5] = (*v >> 40) as u8;
-        a[4] = (*v >> 32) as u8;
-        a[3] = (*v >> 24) as u8;
-        a[2] = (*v >> 16) as u8;
-        a[1] = (*v >> 8) as u8;
-        a[0] = *v as u8;
+    const SIZE: usize = 8;
+
+    fn try_to_u8_be(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        check_len(a, 8)?;
+        let be = v.to_be();
+        unsafe { copy_nonoverlapping(&be as *const u64 as *const u8, a.as_mut_ptr(), 8) };
+        Ok(())
+    }
+
+    fn try_to_u8_le(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        check_len(a, 8)?;
+        let le = v.to_le();
+        unsafe { copy_nonoverlapping(&le as *const u64 as *const u8, a.as_mut_ptr(), 8) };
+        Ok(())
     }
 }
 
 impl Readable for bool {
-    fn from_u8_be(i: &[u8]) -> Self {
-        i[0] > 0
+    const SIZE: usize = 1;
+
+    fn try_from_u8_be(i: &[u8]) -> Result<Self, ByteError> {
+        check_len(i, 1)?;
+        Ok(i[0] > 0)
     }
 
-    fn from_u8_le(i: &[u8]) -> Self {
-        i[0] > 0
+    fn try_from_u8_le(i: &[u8]) -> Result<Self, ByteError> {
+        check_len(i, 1)?;
+        Ok(i[0] > 0)
     }
 }
 
 impl Writeable for bool {
-    fn to_u8_be(v: &Self, a: &mut [u8]) {
+    const SIZE: usize = 1;
+
+    fn try_to_u8_be(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        check_len(a, 1)?;
         a[0] = if *v {
             1u8
         } else {
             0u8
         };
+        Ok(())
     }
 
-    fn to_u8_le(v: &Self, a: &mut [u8]) {
+    fn try_to_u8_le(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        check_len(a, 1)?;
         a[0] = if *v {
             1u8
         } else {
             0u8
         };
+        Ok(())
     }
 }
 
 impl Readable for f32 {
-    fn from_u8_be(i: &[u8]) -> Self {
-        unsafe { transmute(u32::from_u8_be(i)) }
+    const SIZE: usize = 4;
+
+    fn try_from_u8_be(i: &[u8]) -> Result<Self, ByteError> {
+        Ok(f32::from_bits(u32::try_from_u8_be(i)?))
     }
 
-    fn from_u8_le(i: &[u8]) -> Self {
-        unsafe { transmute(u32::from_u8_le(i)) }
+    fn try_from_u8_le(i: &[u8]) -> Result<Self, ByteError> {
+        Ok(f32::from_bits(u32::try_from_u8_le(i)?))
     }
 }
 
 impl Writeable for f32 {
-    fn to_u8_be(v: &Self, a: &mut [u8]) {
-        unsafe { u32::to_u8_be(transmute(v), a) }
+    const SIZE: usize = 4;
+
+    fn try_to_u8_be(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        u32::try_to_u8_be(&v.to_bits(), a)
     }
 
-    fn to_u8_le(v: &Self, a: &mut [u8]) {
-        unsafe { u32::to_u8_le(transmute(v), a) }
+    fn try_to_u8_le(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        u32::try_to_u8_le(&v.to_bits(), a)
     }
 }
 
 impl Readable for f64 {
-    fn from_u8_be(i: &[u8]) -> Self {
-        unsafe { transmute(u64::from_u8_be(i)) }
+    const SIZE: usize = 8;
+
+    fn try_from_u8_be(i: &[u8]) -> Result<Self, ByteError> {
+        Ok(f64::from_bits(u64::try_from_u8_be(i)?))
     }
 
-    fn from_u8_le(i: &[u8]) -> Self {
-        unsafe { transmute(u64::from_u8_le(i)) }
+    fn try_from_u8_le(i: &[u8]) -> Result<Self, ByteError> {
+        Ok(f64::from_bits(u64::try_from_u8_le(i)?))
     }
 }
 
 impl Writeable for f64 {
-    fn to_u8_be(v: &Self, a: &mut [u8]) {
-        unsafe { u64::to_u8_be(transmute(v), a) }
+    const SIZE: usize = 8;
+
+    fn try_to_u8_be(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        u64::try_to_u8_be(&v.to_bits(), a)
+    }
+
+    fn try_to_u8_le(v: &Self, a: &mut [u8]) -> Result<(), ByteError> {
+        u64::try_to_u8_le(&v.to_bits(), a)
+    }
+}
+
+/// write a number to stream using LEB128 variable-length encoding.
+///
+/// returns the number of bytes written. panics if `buffer` is not large
+/// enough to hold the encoded value (see `max_leb128_len`).
+///
+/// ```
+/// use byte_io::*;
+///
+/// let mut buf = [0u8; 4];
+/// let n = write_leb128(&300u32, &mut buf);
+/// assert_eq!(n, 2);
+/// assert_eq!(&buf[..n], [0xAC, 0x02]);
+/// ```
+pub fn write_leb128<T: Leb128>(v: &T, buffer: &mut [u8]) -> usize {
+    T::write_leb128(v, buffer)
+}
+
+/// read a number from stream using LEB128 variable-length encoding.
+///
+/// returns the decoded value and the number of bytes consumed, so the
+/// caller can advance past it.
+///
+/// panics if `buffer` runs out before a terminating byte is found, or if
+/// the encoding is malformed (more continuation bytes than any valid
+/// varint needs). See `try_read_leb128` for a fallible version suitable
+/// for untrusted input.
+///
+/// ```
+/// use byte_io::*;
+///
+/// let data = [0xAC, 0x02, 0xFF];
+/// let (v, n) = read_leb128::<u32>(&data);
+/// assert_eq!(v, 300);
+/// assert_eq!(n, 2);
+/// ```
+pub fn read_leb128<T: Leb128>(buffer: &[u8]) -> (T, usize) {
+    T::read_leb128(buffer)
+}
+
+/// read a number from stream using LEB128 variable-length encoding,
+/// without panicking.
+///
+/// returns `Err(ByteError::UnexpectedEof { .. })` if `buffer` runs out
+/// before a terminating byte is found, or `Err(ByteError::Leb128Overflow
+/// { .. })` if the encoding uses more continuation bytes than any valid
+/// varint needs (malformed or adversarial input).
+///
+/// ```
+/// use byte_io::*;
+///
+/// assert!(try_read_leb128::<u32>(&[0x80, 0x80, 0x80]).is_err());
+/// assert!(try_read_leb128::<u64>(&[0x80u8; 20]).is_err());
+/// assert_eq!(try_read_leb128::<u32>(&[0xAC, 0x02]), Ok((300, 2)));
+///
+/// // the overflow bound is per-type, not the widest type: a `u8` varint
+/// // can never need more than `max_leb128_len::<u8>()` (2) bytes.
+/// assert_eq!(
+///     try_read_leb128::<u8>(&[0x80, 0x80, 0x80]),
+///     Err(ByteError::Leb128Overflow { max_bytes: 2 })
+/// );
+/// ```
+pub fn try_read_leb128<T: Leb128>(buffer: &[u8]) -> Result<(T, usize), ByteError> {
+    T::try_read_leb128(buffer)
+}
+
+/// the maximum number of bytes `write_leb128::<T>` can ever produce, useful
+/// for sizing a buffer up front.
+///
+/// ```
+/// use byte_io::*;
+///
+/// assert_eq!(max_leb128_len::<u8>(), 2);
+/// assert_eq!(max_leb128_len::<u64>(), 10);
+/// ```
+pub fn max_leb128_len<T>() -> usize {
+    (size_of::<T>() * 8).div_ceil(7)
+}
+
+/// Any type implementing Leb128 can be encoded/decoded using the LEB128
+/// variable-length format instead of the fixed-width `Readable`/`Writeable`
+/// encoding.
+///
+/// Unsigned values take the low 7 bits of the value per byte, setting the
+/// continuation bit (`0x80`) on every byte but the last. Signed values use
+/// sign-extension: encoding stops once the remaining value is entirely made
+/// up of the sign bits of the last emitted byte, i.e. it is `0` with the
+/// byte's sign bit clear, or `-1` with the byte's sign bit set.
+pub trait Leb128: marker::Sized {
+    fn write_leb128(&self, buffer: &mut [u8]) -> usize;
+
+    /// panics if `buffer` does not contain a complete, well-formed varint.
+    /// See `try_read_leb128` for a fallible version.
+    fn read_leb128(buffer: &[u8]) -> (Self, usize) {
+        Self::try_read_leb128(buffer).unwrap()
+    }
+
+    fn try_read_leb128(buffer: &[u8]) -> Result<(Self, usize), ByteError>;
+}
+
+fn write_unsigned_leb128(mut v: u64, buffer: &mut [u8]) -> usize {
+    let mut i = 0;
+    loop {
+        let mut byte = (v & 0x7F) as u8;
+        v >>= 7;
+        if v != 0 {
+            byte |= 0x80;
+        }
+        buffer[i] = byte;
+        i += 1;
+        if v == 0 {
+            return i;
+        }
+    }
+}
+
+fn try_read_unsigned_leb128(buffer: &[u8], max_bytes: usize) -> Result<(u64, usize), ByteError> {
+    let mut result: u64 = 0;
+    let mut i = 0;
+    loop {
+        if i >= max_bytes {
+            return Err(ByteError::Leb128Overflow { max_bytes });
+        }
+        let byte = *buffer.get(i).ok_or(ByteError::UnexpectedEof {
+            needed: i + 1,
+            got: buffer.len(),
+        })?;
+        result |= ((byte & 0x7F) as u64) << (7 * i);
+        i += 1;
+        if byte & 0x80 == 0 {
+            return Ok((result, i));
+        }
+    }
+}
+
+fn write_signed_leb128(mut v: i64, buffer: &mut [u8]) -> usize {
+    let mut i = 0;
+    loop {
+        let mut byte = (v & 0x7F) as u8;
+        v >>= 7;
+        let done = (v == 0 && byte & 0x40 == 0) || (v == -1 && byte & 0x40 != 0);
+        if !done {
+            byte |= 0x80;
+        }
+        buffer[i] = byte;
+        i += 1;
+        if done {
+            return i;
+        }
+    }
+}
+
+fn try_read_signed_leb128(buffer: &[u8], max_bytes: usize) -> Result<(i64, usize), ByteError> {
+    let mut result: i64 = 0;
+    let mut shift = 0;
+    let mut i = 0;
+    loop {
+        if i >= max_bytes {
+            return Err(ByteError::Leb128Overflow { max_bytes });
+        }
+        let byte = *buffer.get(i).ok_or(ByteError::UnexpectedEof {
+            needed: i + 1,
+            got: buffer.len(),
+        })?;
+        result |= ((byte & 0x7F) as i64) << shift;
+        shift += 7;
+        i += 1;
+        if byte & 0x80 == 0 {
+            if shift < 64 && byte & 0x40 != 0 {
+                result |= -1i64 << shift;
+            }
+            return Ok((result, i));
+        }
+    }
+}
+
+impl Leb128 for u8 {
+    fn write_leb128(&self, buffer: &mut [u8]) -> usize {
+        write_unsigned_leb128(*self as u64, buffer)
+    }
+
+    fn try_read_leb128(buffer: &[u8]) -> Result<(Self, usize), ByteError> {
+        let (v, n) = try_read_unsigned_leb128(buffer, max_leb128_len::<Self>())?;
+        Ok((v as u8, n))
+    }
+}
+
+impl Leb128 for u16 {
+    fn write_leb128(&self, buffer: &mut [u8]) -> usize {
+        write_unsigned_leb128(*self as u64, buffer)
+    }
+
+    fn try_read_leb128(buffer: &[u8]) -> Result<(Self, usize), ByteError> {
+        let (v, n) = try_read_unsigned_leb128(buffer, max_leb128_len::<Self>())?;
+        Ok((v as u16, n))
+    }
+}
+
+impl Leb128 for u32 {
+    fn write_leb128(&self, buffer: &mut [u8]) -> usize {
+        write_unsigned_leb128(*self as u64, buffer)
+    }
+
+    fn try_read_leb128(buffer: &[u8]) -> Result<(Self, usize), ByteError> {
+        let (v, n) = try_read_unsigned_leb128(buffer, max_leb128_len::<Self>())?;
+        Ok((v as u32, n))
+    }
+}
+
+impl Leb128 for u64 {
+    fn write_leb128(&self, buffer: &mut [u8]) -> usize {
+        write_unsigned_leb128(*self, buffer)
+    }
+
+    fn try_read_leb128(buffer: &[u8]) -> Result<(Self, usize), ByteError> {
+        try_read_unsigned_leb128(buffer, max_leb128_len::<Self>())
+    }
+}
+
+impl Leb128 for i8 {
+    fn write_leb128(&self, buffer: &mut [u8]) -> usize {
+        write_signed_leb128(*self as i64, buffer)
+    }
+
+    fn try_read_leb128(buffer: &[u8]) -> Result<(Self, usize), ByteError> {
+        let (v, n) = try_read_signed_leb128(buffer, max_leb128_len::<Self>())?;
+        Ok((v as i8, n))
+    }
+}
+
+impl Leb128 for i16 {
+    fn write_leb128(&self, buffer: &mut [u8]) -> usize {
+        write_signed_leb128(*self as i64, buffer)
+    }
+
+    fn try_read_leb128(buffer: &[u8]) -> Result<(Self, usize), ByteError> {
+        let (v, n) = try_read_signed_leb128(buffer, max_leb128_len::<Self>())?;
+        Ok((v as i16, n))
+    }
+}
+
+impl Leb128 for i32 {
+    fn write_leb128(&self, buffer: &mut [u8]) -> usize {
+        write_signed_leb128(*self as i64, buffer)
+    }
+
+    fn try_read_leb128(buffer: &[u8]) -> Result<(Self, usize), ByteError> {
+        let (v, n) = try_read_signed_leb128(buffer, max_leb128_len::<Self>())?;
+        Ok((v as i32, n))
+    }
+}
+
+impl Leb128 for i64 {
+    fn write_leb128(&self, buffer: &mut [u8]) -> usize {
+        write_signed_leb128(*self, buffer)
+    }
+
+    fn try_read_leb128(buffer: &[u8]) -> Result<(Self, usize), ByteError> {
+        try_read_signed_leb128(buffer, max_leb128_len::<Self>())
+    }
+}
+
+/// Reads individual bits (MSB-first) out of a byte slice, for binary
+/// formats that pack flags and small counts into non-byte-aligned fields.
+pub struct BitReader<'a> {
+    buffer: &'a [u8],
+    bit_pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    /// creates a new `BitReader` positioned at the start of `buffer`.
+    ///
+    /// ```
+    /// use byte_io::*;
+    ///
+    /// let data = [0b1010_0000];
+    /// let mut r = BitReader::new(&data);
+    /// assert_eq!(r.read_bits(3), 0b101);
+    /// ```
+    pub fn new(buffer: &'a [u8]) -> Self {
+        BitReader { buffer, bit_pos: 0 }
+    }
+
+    /// reads the next `n` bits (MSB-first) as a `u64`, advancing the cursor.
+    ///
+    /// panics if `n` is greater than 64 or the buffer runs out of bits.
+    ///
+    /// ```
+    /// use byte_io::*;
+    ///
+    /// let data = [0b1100_1010];
+    /// let mut r = BitReader::new(&data);
+    /// assert_eq!(r.read_bits(4), 0b1100);
+    /// assert_eq!(r.read_bits(4), 0b1010);
+    /// ```
+    pub fn read_bits(&mut self, n: u8) -> u64 {
+        assert!(n <= 64);
+        let mut result: u64 = 0;
+        for _ in 0..n {
+            let byte_index = self.bit_pos / 8;
+            let bit_index = 7 - (self.bit_pos % 8);
+            let bit = (self.buffer[byte_index] >> bit_index) & 1;
+            result = (result << 1) | bit as u64;
+            self.bit_pos += 1;
+        }
+        result
+    }
+
+    /// advances the cursor to the start of the next byte, if it is not
+    /// already byte-aligned.
+    pub fn align(&mut self) {
+        self.bit_pos = self.bit_pos.div_ceil(8) * 8;
+    }
+
+    /// reads a `T` from the buffer. Takes a fast path (a direct
+    /// `Readable::from_u8_be` over the underlying slice) when the cursor is
+    /// byte-aligned, and otherwise assembles the bytes bit-by-bit.
+    ///
+    /// ```
+    /// use byte_io::*;
+    ///
+    /// let data = [0x01, 0x02];
+    /// let mut r = BitReader::new(&data);
+    /// assert_eq!(r.read::<u16>(), 0x0102);
+    /// ```
+    pub fn read<T: Readable>(&mut self) -> T {
+        let n_bytes = T::SIZE;
+        if self.bit_pos.is_multiple_of(8) {
+            let byte_index = self.bit_pos / 8;
+            let v = T::from_u8_be(&self.buffer[byte_index..byte_index + n_bytes]);
+            self.bit_pos += n_bytes * 8;
+            v
+        } else {
+            let mut bytes = vec![0u8; n_bytes];
+            for b in bytes.iter_mut() {
+                *b = self.read_bits(8) as u8;
+            }
+            T::from_u8_be(&bytes)
+        }
+    }
+}
+
+/// Writes individual bits (MSB-first) into a byte slice, for binary
+/// formats that pack flags and small counts into non-byte-aligned fields.
+pub struct BitWriter<'a> {
+    buffer: &'a mut [u8],
+    bit_pos: usize,
+}
+
+impl<'a> BitWriter<'a> {
+    /// creates a new `BitWriter` positioned at the start of `buffer`.
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        BitWriter { buffer, bit_pos: 0 }
+    }
+
+    /// writes the low `n` bits of `value` (MSB-first), advancing the
+    /// cursor.
+    ///
+    /// panics if `n` is greater than 64 or the buffer runs out of bits.
+    ///
+    /// ```
+    /// use byte_io::*;
+    ///
+    /// let mut buf = [0u8; 1];
+    /// let mut w = BitWriter::new(&mut buf);
+    /// w.write_bits(0b1100, 4);
+    /// w.write_bits(0b1010, 4);
+    /// assert_eq!(buf, [0b1100_1010]);
+    /// ```
+    pub fn write_bits(&mut self, value: u64, n: u8) {
+        assert!(n <= 64);
+        for i in (0..n).rev() {
+            let bit = ((value >> i) & 1) as u8;
+            let byte_index = self.bit_pos / 8;
+            let bit_index = 7 - (self.bit_pos % 8);
+            if bit == 1 {
+                self.buffer[byte_index] |= 1 << bit_index;
+            } else {
+                self.buffer[byte_index] &= !(1 << bit_index);
+            }
+            self.bit_pos += 1;
+        }
     }
 
-    fn to_u8_le(v: &Self, a: &mut [u8]) {
-        unsafe { u64::to_u8_le(transmute(v), a) }
+    /// advances the cursor to the start of the next byte, if it is not
+    /// already byte-aligned.
+    pub fn align(&mut self) {
+        self.bit_pos = self.bit_pos.div_ceil(8) * 8;
     }
 }